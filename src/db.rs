@@ -1,5 +1,5 @@
 use anyhow::{Context, Result};
-use rusqlite::{params, Connection, Transaction};
+use rusqlite::{params, Connection, OptionalExtension, Transaction};
 use std::path::Path;
 
 use crate::models::{CommitInfo, RepositoryStats};
@@ -7,14 +7,24 @@ use crate::models::{CommitInfo, RepositoryStats};
 /// Initialize the SQLite database
 pub fn init_db() -> Result<Connection> {
     let db_path = Path::new("repo_activity.db");
-    
+
     // 连接到数据库
     let conn = Connection::open(db_path)
         .context("Failed to connect to SQLite database")?;
-    
+
+    init_schema(&conn)?;
+
+    Ok(conn)
+}
+
+/// Creates every table/index this crate relies on (idempotent via
+/// `CREATE TABLE IF NOT EXISTS`) and runs the in-place migrations for
+/// pre-existing databases. Split out from `init_db` so tests can stand up an
+/// identical schema against an in-memory connection.
+fn init_schema(conn: &Connection) -> Result<()> {
     // 启用外键约束
     conn.execute("PRAGMA foreign_keys = ON", [])?;
-    
+
     // 创建表（如果不存在）
     conn.execute("
         CREATE TABLE IF NOT EXISTS repositories (
@@ -25,6 +35,7 @@ pub fn init_db() -> Result<Connection> {
             total_lines_removed INTEGER NOT NULL,
             first_commit_date TEXT NOT NULL,
             last_commit_date TEXT NOT NULL,
+            last_indexed_hash TEXT,
             created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
         )
     ", [])
@@ -35,12 +46,13 @@ pub fn init_db() -> Result<Connection> {
             id INTEGER PRIMARY KEY AUTOINCREMENT,
             repository_id INTEGER NOT NULL,
             name TEXT NOT NULL,
-            email TEXT,
+            identity_key TEXT NOT NULL,
             commits INTEGER NOT NULL,
             lines_added INTEGER NOT NULL,
             lines_removed INTEGER NOT NULL,
             first_commit_date TEXT NOT NULL,
             last_commit_date TEXT NOT NULL,
+            breaking_changes INTEGER NOT NULL DEFAULT 0,
             FOREIGN KEY (repository_id) REFERENCES repositories(id)
         )
     ", [])
@@ -58,71 +70,290 @@ pub fn init_db() -> Result<Connection> {
             lines_added INTEGER NOT NULL,
             lines_removed INTEGER NOT NULL,
             files_changed INTEGER NOT NULL,
+            commit_type TEXT,
+            scope TEXT,
+            breaking INTEGER NOT NULL DEFAULT 0,
             FOREIGN KEY (repository_id) REFERENCES repositories(id)
         )
     ", [])
     .context("Failed to create commits table")?;
-    
-    Ok(conn)
+
+    conn.execute("
+        CREATE TABLE IF NOT EXISTS commit_files (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            commit_id INTEGER NOT NULL,
+            path TEXT NOT NULL,
+            lines_added INTEGER NOT NULL DEFAULT 0,
+            lines_removed INTEGER NOT NULL DEFAULT 0,
+            FOREIGN KEY (commit_id) REFERENCES commits(id)
+        )
+    ", [])
+    .context("Failed to create commit_files table")?;
+
+    conn.execute("
+        CREATE TABLE IF NOT EXISTS file_stats (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            repository_id INTEGER NOT NULL,
+            path TEXT NOT NULL,
+            touches INTEGER NOT NULL,
+            lines_added INTEGER NOT NULL,
+            lines_removed INTEGER NOT NULL,
+            authors TEXT NOT NULL DEFAULT '',
+            last_modified TEXT NOT NULL,
+            FOREIGN KEY (repository_id) REFERENCES repositories(id)
+        )
+    ", [])
+    .context("Failed to create file_stats table")?;
+
+    conn.execute("
+        CREATE TABLE IF NOT EXISTS github_issues (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            repository_id INTEGER NOT NULL,
+            number INTEGER NOT NULL,
+            title TEXT NOT NULL,
+            author TEXT,
+            state TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            closed_at TEXT,
+            FOREIGN KEY (repository_id) REFERENCES repositories(id)
+        )
+    ", [])
+    .context("Failed to create github_issues table")?;
+
+    conn.execute("
+        CREATE TABLE IF NOT EXISTS github_pull_requests (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            repository_id INTEGER NOT NULL,
+            number INTEGER NOT NULL,
+            title TEXT NOT NULL,
+            author TEXT,
+            state TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            closed_at TEXT,
+            FOREIGN KEY (repository_id) REFERENCES repositories(id)
+        )
+    ", [])
+    .context("Failed to create github_pull_requests table")?;
+
+    // Keyset pagination over `commits` filters/orders by date, so index it
+    // up front rather than falling back to OFFSET scans on large histories.
+    conn.execute("CREATE INDEX IF NOT EXISTS idx_commits_date ON commits(date)", [])
+        .context("Failed to create commits date index")?;
+    conn.execute("CREATE INDEX IF NOT EXISTS idx_commit_files_path ON commit_files(path)", [])
+        .context("Failed to create commit_files path index")?;
+
+    // `CREATE TABLE IF NOT EXISTS` above is a no-op against a `repo_activity.db`
+    // created by an older build, so columns added by later features (the
+    // Conventional Commits rollups and incremental-indexing watermark) need
+    // their own migration for anyone upgrading in place.
+    migrate_schema(conn).context("Failed to migrate database schema")?;
+
+    Ok(())
+}
+
+/// Adds columns introduced after the initial schema to a pre-existing
+/// database. Each `ALTER TABLE ... ADD COLUMN` is attempted unconditionally
+/// and a "duplicate column name" failure is treated as already-migrated,
+/// since SQLite has no `ADD COLUMN IF NOT EXISTS`.
+fn migrate_schema(conn: &Connection) -> Result<()> {
+    let migrations = [
+        ("repositories", "last_indexed_hash", "ALTER TABLE repositories ADD COLUMN last_indexed_hash TEXT"),
+        ("contributors", "breaking_changes", "ALTER TABLE contributors ADD COLUMN breaking_changes INTEGER NOT NULL DEFAULT 0"),
+        ("commits", "commit_type", "ALTER TABLE commits ADD COLUMN commit_type TEXT"),
+        ("commits", "scope", "ALTER TABLE commits ADD COLUMN scope TEXT"),
+        ("commits", "breaking", "ALTER TABLE commits ADD COLUMN breaking INTEGER NOT NULL DEFAULT 0"),
+    ];
+
+    for (table, column, ddl) in migrations {
+        match conn.execute(ddl, []) {
+            Ok(_) => {}
+            Err(rusqlite::Error::SqliteFailure(_, Some(msg))) if msg.contains("duplicate column name") => {}
+            Err(e) => {
+                return Err(e).with_context(|| format!("Failed to add column {} to {}", column, table));
+            }
+        }
+    }
+
+    // Earlier releases stored the contributor identity key (a normalized
+    // email, or a `"name:<display name>"` sentinel for no-email authors) in a
+    // column literally named `email`, which misdescribes what it holds and
+    // diverges from `commits.email` (the raw, unnormalized per-commit email).
+    // Rename it on databases that still have the old column; new installs
+    // already get `identity_key` from `CREATE TABLE` above, so the rename has
+    // nothing to do and fails with "no such column", which we treat as
+    // already-migrated.
+    match conn.execute("ALTER TABLE contributors RENAME COLUMN email TO identity_key", []) {
+        Ok(_) => {}
+        Err(rusqlite::Error::SqliteFailure(_, Some(msg))) if msg.contains("no such column") => {}
+        Err(e) => return Err(e).context("Failed to rename contributors.email to identity_key"),
+    }
+
+    Ok(())
+}
+
+/// Looks up the hash of the most recently indexed commit for `repo_path`, if
+/// this repository has been analyzed before. `analyze_repository` uses this
+/// to stop its revwalk early and only diff commits that are actually new.
+pub fn get_last_indexed_hash(conn: &Connection, repo_path: &str) -> Result<Option<String>> {
+    conn.query_row(
+        "SELECT last_indexed_hash FROM repositories WHERE path = ?",
+        params![repo_path],
+        |row| row.get::<_, Option<String>>(0),
+    )
+    .optional()
+    .context("Failed to look up last indexed commit hash")
+    .map(|row| row.flatten())
 }
 
 /// Save repository statistics to the database
-pub fn save_stats(mut conn: Connection, stats: &RepositoryStats) -> Result<()> {
+/// If `stats.repo_path` has
+/// been indexed before, this merges `stats` (which should only contain
+/// newly-discovered commits) into the existing aggregates and appends the
+/// new commit/contributor rows, rather than creating a duplicate repository.
+pub fn save_stats(conn: &mut Connection, stats: &RepositoryStats) -> Result<i64> {
     // 开始事务
     let tx = conn.transaction()
         .context("Failed to start database transaction")?;
-    
-    // 插入仓库信息
-    tx.execute(
-        "INSERT INTO repositories 
-            (path, total_commits, total_lines_added, total_lines_removed, first_commit_date, last_commit_date) 
-         VALUES (?, ?, ?, ?, ?, ?)",
-        params![
-            &stats.repo_path,
-            stats.total_commits as i64,
-            stats.total_lines_added as i64,
-            stats.total_lines_removed as i64,
-            stats.first_commit_date.to_rfc3339(),
-            stats.last_commit_date.to_rfc3339()
-        ],
-    )
-    .context("Failed to insert repository info")?;
-    
-    // 获取插入的仓库 ID
-    let repo_id = tx.last_insert_rowid();
-    
-    // 插入贡献者信息
-    for (name, contributor) in &stats.contributors {
-        // 查找该贡献者的邮箱
-        let email = stats.commits.iter()
-            .find(|commit| commit.author == *name)
-            .map(|commit| commit.email.clone())
-            .unwrap_or_default();
-            
-        tx.execute(
-            r#"INSERT INTO contributors 
-                (repository_id, name, email, commits, lines_added, lines_removed, first_commit_date, last_commit_date) 
-             VALUES (?, ?, ?, ?, ?, ?, ?, ?)"#,
-            params![
-                repo_id,
-                name,
-                email,
-                contributor.commits as i64,
-                contributor.lines_added as i64,
-                contributor.lines_removed as i64,
-                contributor.first_commit.to_rfc3339(),
-                contributor.last_commit.to_rfc3339()
-            ],
+
+    let existing: Option<(i64, i64, i64, i64, String, String)> = tx
+        .query_row(
+            "SELECT id, total_commits, total_lines_added, total_lines_removed, first_commit_date, last_commit_date
+             FROM repositories WHERE path = ?",
+            params![&stats.repo_path],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?, row.get(5)?)),
         )
-        .context("Failed to insert contributor info")?;
+        .optional()
+        .context("Failed to look up existing repository row")?;
+
+    // The revwalk tip (HEAD at the time of this run) becomes the new
+    // `last_indexed_hash`, not the newest-by-author-date commit in this
+    // batch: author dates aren't guaranteed to agree with topology (rebases,
+    // cherry-picks, clock skew, merges pulling in older-dated commits), so a
+    // date-based pick can land on an ancestor of HEAD and cause the next run
+    // to re-walk and re-insert everything in between. If this run recorded no
+    // tip (a date-filtered run), keep whatever was already recorded.
+    let latest_hash = stats.head_hash.clone();
+
+    let repo_id = match existing {
+        Some((id, old_commits, old_added, old_removed, old_first_str, old_last_str)) => {
+            let old_first = chrono::DateTime::parse_from_rfc3339(&old_first_str)
+                .context("Failed to parse existing first commit date")?
+                .with_timezone(&chrono::Utc);
+            let old_last = chrono::DateTime::parse_from_rfc3339(&old_last_str)
+                .context("Failed to parse existing last commit date")?
+                .with_timezone(&chrono::Utc);
+
+            let first_commit_date = if stats.total_commits > 0 { old_first.min(stats.first_commit_date) } else { old_first };
+            let last_commit_date = if stats.total_commits > 0 { old_last.max(stats.last_commit_date) } else { old_last };
+
+            tx.execute(
+                "UPDATE repositories
+                    SET total_commits = ?, total_lines_added = ?, total_lines_removed = ?,
+                        first_commit_date = ?, last_commit_date = ?,
+                        last_indexed_hash = COALESCE(?, last_indexed_hash)
+                 WHERE id = ?",
+                params![
+                    old_commits + stats.total_commits as i64,
+                    old_added + stats.total_lines_added as i64,
+                    old_removed + stats.total_lines_removed as i64,
+                    first_commit_date.to_rfc3339(),
+                    last_commit_date.to_rfc3339(),
+                    latest_hash,
+                    id
+                ],
+            )
+            .context("Failed to update repository info")?;
+
+            id
+        }
+        None => {
+            tx.execute(
+                "INSERT INTO repositories
+                    (path, total_commits, total_lines_added, total_lines_removed, first_commit_date, last_commit_date, last_indexed_hash)
+                 VALUES (?, ?, ?, ?, ?, ?, ?)",
+                params![
+                    &stats.repo_path,
+                    stats.total_commits as i64,
+                    stats.total_lines_added as i64,
+                    stats.total_lines_removed as i64,
+                    stats.first_commit_date.to_rfc3339(),
+                    stats.last_commit_date.to_rfc3339(),
+                    latest_hash
+                ],
+            )
+            .context("Failed to insert repository info")?;
+
+            tx.last_insert_rowid()
+        }
+    };
+
+    // 插入/合并贡献者信息（按规范化邮箱合并别名，而不是按显示名）
+    for (identity_key, contributor) in &stats.contributors {
+        let existing_contributor: Option<(i64, i64, i64, i64, String, String, i64)> = tx
+            .query_row(
+                "SELECT id, commits, lines_added, lines_removed, first_commit_date, last_commit_date, breaking_changes
+                 FROM contributors WHERE repository_id = ? AND identity_key = ?",
+                params![repo_id, identity_key],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?, row.get(5)?, row.get(6)?)),
+            )
+            .optional()
+            .context("Failed to look up existing contributor row")?;
+
+        match existing_contributor {
+            Some((id, old_commits, old_added, old_removed, old_first_str, old_last_str, old_breaking)) => {
+                let old_first = chrono::DateTime::parse_from_rfc3339(&old_first_str)
+                    .context("Failed to parse existing contributor first commit date")?
+                    .with_timezone(&chrono::Utc);
+                let old_last = chrono::DateTime::parse_from_rfc3339(&old_last_str)
+                    .context("Failed to parse existing contributor last commit date")?
+                    .with_timezone(&chrono::Utc);
+
+                tx.execute(
+                    "UPDATE contributors
+                        SET name = ?, commits = ?, lines_added = ?, lines_removed = ?,
+                            first_commit_date = ?, last_commit_date = ?, breaking_changes = ?
+                     WHERE id = ?",
+                    params![
+                        &contributor.name,
+                        old_commits + contributor.commits as i64,
+                        old_added + contributor.lines_added as i64,
+                        old_removed + contributor.lines_removed as i64,
+                        old_first.min(contributor.first_commit).to_rfc3339(),
+                        old_last.max(contributor.last_commit).to_rfc3339(),
+                        old_breaking + contributor.breaking_changes as i64,
+                        id
+                    ],
+                )
+                .context("Failed to update contributor info")?;
+            }
+            None => {
+                tx.execute(
+                    r#"INSERT INTO contributors
+                        (repository_id, name, identity_key, commits, lines_added, lines_removed, first_commit_date, last_commit_date, breaking_changes)
+                     VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)"#,
+                    params![
+                        repo_id,
+                        &contributor.name,
+                        identity_key,
+                        contributor.commits as i64,
+                        contributor.lines_added as i64,
+                        contributor.lines_removed as i64,
+                        contributor.first_commit.to_rfc3339(),
+                        contributor.last_commit.to_rfc3339(),
+                        contributor.breaking_changes as i64
+                    ],
+                )
+                .context("Failed to insert contributor info")?;
+            }
+        }
     }
-    
-    // 插入提交信息
+
+    // 插入新提交信息（增量索引：调用方只传入尚未入库的提交）
     for commit in &stats.commits {
         tx.execute(
-            "INSERT INTO commits 
-                (repository_id, hash, author, email, date, message, lines_added, lines_removed, files_changed) 
-             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            "INSERT INTO commits
+                (repository_id, hash, author, email, date, message, lines_added, lines_removed, files_changed, commit_type, scope, breaking)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
             params![
                 repo_id,
                 &commit.hash,
@@ -132,16 +363,199 @@ pub fn save_stats(mut conn: Connection, stats: &RepositoryStats) -> Result<()> {
                 &commit.message,
                 commit.lines_added as i64,
                 commit.lines_removed as i64,
-                commit.files_changed as i64
+                commit.files_changed as i64,
+                &commit.commit_type,
+                &commit.scope,
+                commit.breaking
             ],
         )
         .context("Failed to insert commit info")?;
+
+        let commit_id = tx.last_insert_rowid();
+        for file_change in &commit.file_changes {
+            tx.execute(
+                "INSERT INTO commit_files (commit_id, path, lines_added, lines_removed) VALUES (?, ?, ?, ?)",
+                params![commit_id, &file_change.path, file_change.lines_added as i64, file_change.lines_removed as i64],
+            )
+            .context("Failed to insert commit file change")?;
+        }
     }
-    
+
+    // 插入/合并文件热点信息
+    for (path, file_stat) in &stats.file_stats {
+        let existing_file: Option<(i64, i64, i64, i64, String, String)> = tx
+            .query_row(
+                "SELECT id, touches, lines_added, lines_removed, authors, last_modified
+                 FROM file_stats WHERE repository_id = ? AND path = ?",
+                params![repo_id, path],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?, row.get(5)?)),
+            )
+            .optional()
+            .context("Failed to look up existing file_stats row")?;
+
+        let mut authors: std::collections::BTreeSet<String> = file_stat.authors.iter().cloned().collect();
+
+        match existing_file {
+            Some((id, old_touches, old_added, old_removed, old_authors_str, old_last_modified_str)) => {
+                // Authors are '\n'-joined, not comma-joined: git signature
+                // names are single-line but commonly contain commas (e.g.
+                // "Doe, John"), which a comma delimiter would wrongly split.
+                authors.extend(old_authors_str.split('\n').filter(|s| !s.is_empty()).map(str::to_string));
+
+                let old_last_modified = chrono::DateTime::parse_from_rfc3339(&old_last_modified_str)
+                    .context("Failed to parse existing file_stats last_modified")?
+                    .with_timezone(&chrono::Utc);
+                let last_modified = old_last_modified.max(file_stat.last_modified);
+
+                tx.execute(
+                    "UPDATE file_stats
+                        SET touches = ?, lines_added = ?, lines_removed = ?, authors = ?, last_modified = ?
+                     WHERE id = ?",
+                    params![
+                        old_touches + file_stat.touches as i64,
+                        old_added + file_stat.lines_added as i64,
+                        old_removed + file_stat.lines_removed as i64,
+                        authors.into_iter().collect::<Vec<_>>().join("\n"),
+                        last_modified.to_rfc3339(),
+                        id
+                    ],
+                )
+                .context("Failed to update file_stats row")?;
+            }
+            None => {
+                tx.execute(
+                    "INSERT INTO file_stats (repository_id, path, touches, lines_added, lines_removed, authors, last_modified)
+                     VALUES (?, ?, ?, ?, ?, ?, ?)",
+                    params![
+                        repo_id,
+                        path,
+                        file_stat.touches as i64,
+                        file_stat.lines_added as i64,
+                        file_stat.lines_removed as i64,
+                        authors.into_iter().collect::<Vec<_>>().join("\n"),
+                        file_stat.last_modified.to_rfc3339()
+                    ],
+                )
+                .context("Failed to insert file_stats row")?;
+            }
+        }
+    }
+
     // 提交事务
     tx.commit()
         .context("Failed to commit database transaction")?;
-    
+
+    Ok(repo_id)
+}
+
+/// Persists GitHub issue/PR activity fetched via `github::fetch_repo_activity`
+/// for `repository_id`. Kept separate from `save_stats` so purely-local runs
+/// never touch these tables.
+pub fn save_github_activity(
+    conn: &mut Connection,
+    repository_id: i64,
+    activity: &crate::models::GithubActivity,
+) -> Result<()> {
+    let tx = conn.transaction()
+        .context("Failed to start database transaction")?;
+
+    for issue in &activity.issues {
+        let existing_id: Option<i64> = tx
+            .query_row(
+                "SELECT id FROM github_issues WHERE repository_id = ? AND number = ?",
+                params![repository_id, issue.number],
+                |row| row.get(0),
+            )
+            .optional()
+            .context("Failed to look up existing GitHub issue row")?;
+
+        match existing_id {
+            Some(id) => {
+                tx.execute(
+                    "UPDATE github_issues
+                        SET title = ?, author = ?, state = ?, created_at = ?, closed_at = ?
+                     WHERE id = ?",
+                    params![
+                        &issue.title,
+                        &issue.author,
+                        &issue.state,
+                        issue.created_at.to_rfc3339(),
+                        issue.closed_at.map(|d| d.to_rfc3339()),
+                        id
+                    ],
+                )
+                .context("Failed to update GitHub issue")?;
+            }
+            None => {
+                tx.execute(
+                    "INSERT INTO github_issues
+                        (repository_id, number, title, author, state, created_at, closed_at)
+                     VALUES (?, ?, ?, ?, ?, ?, ?)",
+                    params![
+                        repository_id,
+                        issue.number,
+                        &issue.title,
+                        &issue.author,
+                        &issue.state,
+                        issue.created_at.to_rfc3339(),
+                        issue.closed_at.map(|d| d.to_rfc3339())
+                    ],
+                )
+                .context("Failed to insert GitHub issue")?;
+            }
+        }
+    }
+
+    for pr in &activity.pull_requests {
+        let existing_id: Option<i64> = tx
+            .query_row(
+                "SELECT id FROM github_pull_requests WHERE repository_id = ? AND number = ?",
+                params![repository_id, pr.number],
+                |row| row.get(0),
+            )
+            .optional()
+            .context("Failed to look up existing GitHub pull request row")?;
+
+        match existing_id {
+            Some(id) => {
+                tx.execute(
+                    "UPDATE github_pull_requests
+                        SET title = ?, author = ?, state = ?, created_at = ?, closed_at = ?
+                     WHERE id = ?",
+                    params![
+                        &pr.title,
+                        &pr.author,
+                        &pr.state,
+                        pr.created_at.to_rfc3339(),
+                        pr.closed_at.map(|d| d.to_rfc3339()),
+                        id
+                    ],
+                )
+                .context("Failed to update GitHub pull request")?;
+            }
+            None => {
+                tx.execute(
+                    "INSERT INTO github_pull_requests
+                        (repository_id, number, title, author, state, created_at, closed_at)
+                     VALUES (?, ?, ?, ?, ?, ?, ?)",
+                    params![
+                        repository_id,
+                        pr.number,
+                        &pr.title,
+                        &pr.author,
+                        &pr.state,
+                        pr.created_at.to_rfc3339(),
+                        pr.closed_at.map(|d| d.to_rfc3339())
+                    ],
+                )
+                .context("Failed to insert GitHub pull request")?;
+            }
+        }
+    }
+
+    tx.commit()
+        .context("Failed to commit database transaction")?;
+
     Ok(())
 }
 
@@ -188,59 +602,66 @@ pub fn get_repository_stats(conn: &Connection, repo_id: i64) -> Result<Repositor
         last_commit_date,
         contributors: std::collections::HashMap::new(),
         commits: Vec::new(),
+        commit_type_counts: std::collections::HashMap::new(),
+        breaking_changes: 0,
+        file_stats: std::collections::HashMap::new(),
+        head_hash: None,
     };
-    
+
     // 获取贡献者信息
     let mut stmt = conn.prepare(
-        "SELECT name, email, commits, lines_added, lines_removed, 
-                first_commit_date, last_commit_date 
-         FROM contributors 
+        "SELECT name, identity_key, commits, lines_added, lines_removed,
+                first_commit_date, last_commit_date, breaking_changes
+         FROM contributors
          WHERE repository_id = ?"
     )?;
-    
+
     let contributors = stmt.query_map(params![repo_id], |row| {
         Ok((
             row.get::<_, String>(0)?, // name
-            row.get::<_, Option<String>>(1)?, // email
+            row.get::<_, String>(1)?, // identity_key
             row.get::<_, i64>(2)?,    // commits
             row.get::<_, i64>(3)?,    // lines_added
             row.get::<_, i64>(4)?,    // lines_removed
             row.get::<_, String>(5)?, // first_commit_date
             row.get::<_, String>(6)?, // last_commit_date
+            row.get::<_, i64>(7)?,    // breaking_changes
         ))
     })?;
-    
+
     for contributor_result in contributors {
-        let (name, email, commits, lines_added, lines_removed, 
-             first_commit_str, last_commit_str) = contributor_result?;
-        
+        let (name, identity_key, commits, lines_added, lines_removed,
+             first_commit_str, last_commit_str, breaking_changes) = contributor_result?;
+
         // 解析日期
         let first_commit = chrono::DateTime::parse_from_rfc3339(&first_commit_str)
             .context("Failed to parse contributor first commit date")?
             .with_timezone(&chrono::Utc);
-        
+
         let last_commit = chrono::DateTime::parse_from_rfc3339(&last_commit_str)
             .context("Failed to parse contributor last commit date")?
             .with_timezone(&chrono::Utc);
-        
-        // 添加贡献者
-        stats.contributors.insert(name, crate::models::ContributorStats {
+
+        // 添加贡献者，按 identity_key 存储，与内存中的合并语义保持一致
+        stats.contributors.insert(identity_key, crate::models::ContributorStats {
+            name,
             commits: commits as usize,
             lines_added: lines_added as usize,
             lines_removed: lines_removed as usize,
             first_commit,
             last_commit,
+            breaking_changes: breaking_changes as usize,
         });
     }
-    
+
     // 获取提交信息
     let mut stmt = conn.prepare(
-        "SELECT hash, author, email, date, message, lines_added, lines_removed, files_changed 
-         FROM commits 
-         WHERE repository_id = ? 
+        "SELECT hash, author, email, date, message, lines_added, lines_removed, files_changed, commit_type, scope, breaking
+         FROM commits
+         WHERE repository_id = ?
          ORDER BY date DESC"
     )?;
-    
+
     let commits = stmt.query_map(params![repo_id], |row| {
         Ok(crate::models::CommitInfo {
             hash: row.get(0)?,
@@ -253,12 +674,330 @@ pub fn get_repository_stats(conn: &Connection, repo_id: i64) -> Result<Repositor
             lines_added: row.get::<_, i64>(5)? as usize,
             lines_removed: row.get::<_, i64>(6)? as usize,
             files_changed: row.get::<_, i64>(7)? as usize,
+            commit_type: row.get(8)?,
+            scope: row.get(9)?,
+            breaking: row.get(10)?,
+            file_changes: Vec::new(),
         })
     })?;
-    
+
     for commit_result in commits {
-        stats.commits.push(commit_result?);
+        let commit = commit_result?;
+        if let Some(commit_type) = &commit.commit_type {
+            *stats.commit_type_counts.entry(commit_type.clone()).or_insert(0) += 1;
+        }
+        if commit.breaking {
+            stats.breaking_changes += 1;
+        }
+        stats.commits.push(commit);
     }
-    
+
+    // 获取文件热点信息
+    let mut stmt = conn.prepare(
+        "SELECT path, touches, lines_added, lines_removed, authors, last_modified
+         FROM file_stats
+         WHERE repository_id = ?"
+    )?;
+
+    let file_stats = stmt.query_map(params![repo_id], |row| {
+        Ok((
+            row.get::<_, String>(0)?, // path
+            row.get::<_, i64>(1)?,    // touches
+            row.get::<_, i64>(2)?,    // lines_added
+            row.get::<_, i64>(3)?,    // lines_removed
+            row.get::<_, String>(4)?, // authors
+            row.get::<_, String>(5)?, // last_modified
+        ))
+    })?;
+
+    for file_stat_result in file_stats {
+        let (path, touches, lines_added, lines_removed, authors_str, last_modified_str) = file_stat_result?;
+
+        let last_modified = chrono::DateTime::parse_from_rfc3339(&last_modified_str)
+            .context("Failed to parse file_stats last_modified")?
+            .with_timezone(&chrono::Utc);
+
+        // Authors are '\n'-joined, matching how `save_stats` persists them.
+        let authors = authors_str
+            .split('\n')
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+            .collect();
+
+        stats.file_stats.insert(path, crate::models::FileStats {
+            touches: touches as usize,
+            lines_added: lines_added as usize,
+            lines_removed: lines_removed as usize,
+            authors,
+            last_modified,
+        });
+    }
+
     Ok(stats)
+}
+
+/// Optional filters for querying commits out of the store. Every field left
+/// as `None` is unconstrained; only the filters that are set are folded into
+/// the generated `WHERE` clause, so callers only pay for what they ask for.
+#[derive(Debug, Clone, Default)]
+pub struct CommitFilters {
+    pub author: Option<String>,
+    pub email: Option<String>,
+    pub path_contains: Option<String>,
+    pub message_contains: Option<String>,
+    /// Only commits at or before this date.
+    pub before: Option<chrono::DateTime<chrono::Utc>>,
+    /// Keyset pagination cursor: pass back the cursor for the last commit
+    /// from the previous page (see [`resolve_commit_cursor`]) instead of
+    /// tracking an `OFFSET`. Results are strictly older than the cursor.
+    pub after: Option<CommitCursor>,
+    pub commit_type: Option<String>,
+    pub min_lines_changed: Option<usize>,
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+}
+
+/// A keyset pagination cursor identifying a specific commit by `(date, hash)`.
+/// Carrying the hash alongside the date disambiguates commits that share a
+/// timestamp, which a bare date bound cannot do. `hash` is `None` when the
+/// cursor was given as a bare date, in which case ties at that exact
+/// timestamp are not disambiguated.
+#[derive(Debug, Clone)]
+pub struct CommitCursor {
+    pub date: chrono::DateTime<chrono::Utc>,
+    pub hash: Option<String>,
+}
+
+/// Aggregate totals computed over a filtered set of commits.
+#[derive(Debug, Clone, Default)]
+pub struct CommitQueryTotals {
+    pub total_commits: usize,
+    pub total_lines_added: usize,
+    pub total_lines_removed: usize,
+}
+
+/// Resolves a pagination cursor that is either a full commit hash or an
+/// RFC3339 date string into a concrete `(date, hash)` pair, so callers can
+/// page through results by passing back the hash of the last commit they saw
+/// rather than tracking an `OFFSET`. A bare date string has no associated
+/// hash, so ties at that exact timestamp are not disambiguated.
+pub fn resolve_commit_cursor(conn: &Connection, cursor: &str) -> Result<CommitCursor> {
+    if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(cursor) {
+        return Ok(CommitCursor { date: dt.with_timezone(&chrono::Utc), hash: None });
+    }
+
+    let date_str: String = conn
+        .query_row("SELECT date FROM commits WHERE hash = ?", params![cursor], |row| row.get(0))
+        .context("Cursor is not a valid date and does not match any commit hash")?;
+
+    let date = chrono::DateTime::parse_from_rfc3339(&date_str)
+        .map(|dt| dt.with_timezone(&chrono::Utc))
+        .context("Failed to parse cursor commit date")?;
+
+    Ok(CommitCursor { date, hash: Some(cursor.to_string()) })
+}
+
+/// Queries commits for `repository_id` matching `filters`, returning the
+/// matching commits (most recent first) alongside their aggregate totals.
+pub fn query_commits(
+    conn: &Connection,
+    repository_id: i64,
+    filters: &CommitFilters,
+) -> Result<(Vec<CommitInfo>, CommitQueryTotals)> {
+    let mut where_clauses = vec!["repository_id = ?".to_string()];
+    let mut bindings: Vec<Box<dyn rusqlite::ToSql>> = vec![Box::new(repository_id)];
+
+    if let Some(author) = &filters.author {
+        where_clauses.push("author = ?".to_string());
+        bindings.push(Box::new(author.clone()));
+    }
+    if let Some(email) = &filters.email {
+        where_clauses.push("email = ?".to_string());
+        bindings.push(Box::new(email.clone()));
+    }
+    if let Some(message) = &filters.message_contains {
+        where_clauses.push("message LIKE ?".to_string());
+        bindings.push(Box::new(format!("%{}%", message)));
+    }
+    if let Some(path) = &filters.path_contains {
+        where_clauses.push("id IN (SELECT commit_id FROM commit_files WHERE path LIKE ?)".to_string());
+        bindings.push(Box::new(format!("%{}%", path)));
+    }
+    if let Some(before) = filters.before {
+        where_clauses.push("date <= ?".to_string());
+        bindings.push(Box::new(before.to_rfc3339()));
+    }
+    if let Some(commit_type) = &filters.commit_type {
+        where_clauses.push("commit_type = ?".to_string());
+        bindings.push(Box::new(commit_type.clone()));
+    }
+    if let Some(min_lines_changed) = filters.min_lines_changed {
+        where_clauses.push("(lines_added + lines_removed) >= ?".to_string());
+        bindings.push(Box::new(min_lines_changed as i64));
+    }
+
+    // Aggregate totals are computed from the filter predicates only, *before*
+    // the `after` keyset cursor is folded in below: `after` is a pagination
+    // concern like `limit`/`offset`, not a filter, so it must not shrink the
+    // totals page over page as a caller pages forward.
+    let where_sql = where_clauses.join(" AND ");
+    let param_refs: Vec<&dyn rusqlite::ToSql> = bindings.iter().map(|b| b.as_ref()).collect();
+
+    let totals_sql = format!(
+        "SELECT COUNT(*), COALESCE(SUM(lines_added), 0), COALESCE(SUM(lines_removed), 0)
+         FROM commits WHERE {}",
+        where_sql
+    );
+    let totals = conn
+        .query_row(&totals_sql, param_refs.as_slice(), |row| {
+            Ok(CommitQueryTotals {
+                total_commits: row.get::<_, i64>(0)? as usize,
+                total_lines_added: row.get::<_, i64>(1)? as usize,
+                total_lines_removed: row.get::<_, i64>(2)? as usize,
+            })
+        })
+        .context("Failed to compute totals for filtered commits")?;
+
+    if let Some(after) = &filters.after {
+        // Results are ordered newest-first, so paging forward means finding
+        // commits strictly older than the cursor. A bare `date < ?` bound
+        // would let a commit that shares the cursor's exact timestamp slip
+        // back in on the next page; tie-break on hash when we have one.
+        match &after.hash {
+            Some(hash) => {
+                where_clauses.push("(date < ? OR (date = ? AND hash < ?))".to_string());
+                bindings.push(Box::new(after.date.to_rfc3339()));
+                bindings.push(Box::new(after.date.to_rfc3339()));
+                bindings.push(Box::new(hash.clone()));
+            }
+            None => {
+                where_clauses.push("date < ?".to_string());
+                bindings.push(Box::new(after.date.to_rfc3339()));
+            }
+        }
+    }
+
+    let where_sql = where_clauses.join(" AND ");
+
+    let mut sql = format!(
+        "SELECT hash, author, email, date, message, lines_added, lines_removed, files_changed, commit_type, scope, breaking
+         FROM commits WHERE {} ORDER BY date DESC, hash DESC",
+        where_sql
+    );
+
+    if let Some(limit) = filters.limit {
+        sql.push_str(" LIMIT ?");
+        bindings.push(Box::new(limit as i64));
+    }
+    if let Some(offset) = filters.offset {
+        sql.push_str(" OFFSET ?");
+        bindings.push(Box::new(offset as i64));
+    }
+
+    let mut stmt = conn.prepare(&sql).context("Failed to prepare filtered commit query")?;
+    let param_refs: Vec<&dyn rusqlite::ToSql> = bindings.iter().map(|b| b.as_ref()).collect();
+
+    let commits = stmt
+        .query_map(param_refs.as_slice(), |row| {
+            Ok(CommitInfo {
+                hash: row.get(0)?,
+                author: row.get(1)?,
+                email: row.get(2)?,
+                date: chrono::DateTime::parse_from_rfc3339(&row.get::<_, String>(3)?)
+                    .map(|dt| dt.with_timezone(&chrono::Utc))
+                    .unwrap_or_else(|_| chrono::Utc::now()),
+                message: row.get(4)?,
+                lines_added: row.get::<_, i64>(5)? as usize,
+                lines_removed: row.get::<_, i64>(6)? as usize,
+                files_changed: row.get::<_, i64>(7)? as usize,
+                commit_type: row.get(8)?,
+                scope: row.get(9)?,
+                breaking: row.get(10)?,
+                file_changes: Vec::new(),
+            })
+        })
+        .context("Failed to query filtered commits")?
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .context("Failed to read filtered commits")?;
+
+    Ok((commits, totals))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Builds an in-memory database with the full schema and a single
+    /// repository row, returning the open connection and that repository's id.
+    fn test_repo() -> (Connection, i64) {
+        let conn = Connection::open_in_memory().expect("failed to open in-memory database");
+        init_schema(&conn).expect("failed to create schema");
+
+        conn.execute(
+            "INSERT INTO repositories
+                (path, total_commits, total_lines_added, total_lines_removed, first_commit_date, last_commit_date)
+             VALUES ('/tmp/repo', 0, 0, 0, '2024-01-01T00:00:00Z', '2024-01-01T00:00:00Z')",
+            [],
+        )
+        .expect("failed to insert repository row");
+
+        let repo_id = conn.last_insert_rowid();
+        (conn, repo_id)
+    }
+
+    /// Inserts a commit directly (bypassing `save_stats`) so query tests can
+    /// set up fixtures without going through the whole in-memory aggregation path.
+    fn insert_commit(conn: &Connection, repo_id: i64, hash: &str, date: &str, lines_added: i64, lines_removed: i64) {
+        conn.execute(
+            "INSERT INTO commits
+                (repository_id, hash, author, email, date, message, lines_added, lines_removed, files_changed, commit_type, scope, breaking)
+             VALUES (?, ?, 'Test Author', 'test@example.com', ?, 'test commit', ?, ?, 1, NULL, NULL, 0)",
+            params![repo_id, hash, date, lines_added, lines_removed],
+        )
+        .expect("failed to insert commit row");
+    }
+
+    #[test]
+    fn query_commits_totals_are_unaffected_by_the_after_cursor() {
+        let (conn, repo_id) = test_repo();
+        insert_commit(&conn, repo_id, "c1", "2024-01-01T00:00:00Z", 10, 1);
+        insert_commit(&conn, repo_id, "c2", "2024-01-02T00:00:00Z", 20, 2);
+        insert_commit(&conn, repo_id, "c3", "2024-01-03T00:00:00Z", 30, 3);
+
+        let cursor = resolve_commit_cursor(&conn, "c3").expect("failed to resolve cursor");
+        let filters = CommitFilters { after: Some(cursor), ..Default::default() };
+
+        let (commits, totals) = query_commits(&conn, repo_id, &filters).expect("query_commits failed");
+
+        // Only c1/c2 are strictly older than c3, but the totals must still
+        // reflect all three commits matching the (cursor-less) filter set.
+        assert_eq!(commits.len(), 2);
+        assert_eq!(totals.total_commits, 3);
+        assert_eq!(totals.total_lines_added, 60);
+        assert_eq!(totals.total_lines_removed, 6);
+    }
+
+    #[test]
+    fn query_commits_pages_strictly_older_than_the_cursor_newest_first() {
+        let (conn, repo_id) = test_repo();
+        insert_commit(&conn, repo_id, "c1", "2024-01-01T00:00:00Z", 1, 0);
+        insert_commit(&conn, repo_id, "c2", "2024-01-02T00:00:00Z", 1, 0);
+        insert_commit(&conn, repo_id, "c3", "2024-01-03T00:00:00Z", 1, 0);
+
+        let (first_page, _) = query_commits(&conn, repo_id, &CommitFilters { limit: Some(1), ..Default::default() })
+            .expect("query_commits failed");
+        assert_eq!(first_page.len(), 1);
+        assert_eq!(first_page[0].hash, "c3");
+
+        let cursor = resolve_commit_cursor(&conn, &first_page[0].hash).expect("failed to resolve cursor");
+        let (second_page, _) = query_commits(
+            &conn,
+            repo_id,
+            &CommitFilters { after: Some(cursor), limit: Some(1), ..Default::default() },
+        )
+        .expect("query_commits failed");
+
+        assert_eq!(second_page.len(), 1);
+        assert_eq!(second_page[0].hash, "c2");
+    }
 }
\ No newline at end of file
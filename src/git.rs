@@ -1,67 +1,189 @@
 use anyhow::{anyhow, Context, Result};
-use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
+use chrono::{DateTime, TimeZone, Utc};
 use futures::stream::{self, StreamExt};
-use git2::{Diff, DiffOptions, Repository};
+use git2::{DiffOptions, Mailmap, Repository};
+use std::cell::RefCell;
 use std::path::Path;
-use std::str;
 use tokio::task;
 
-use crate::models::{CommitInfo, RepositoryStats};
+use crate::models::{CommitInfo, FileChange, RepositoryStats};
 
-/// Analyzes a git repository and returns statistics
+thread_local! {
+    /// Each worker thread keeps its own `Repository` and `.mailmap` handle, since
+    /// neither is `Send` and can't be shared across the thread pool. Reopened lazily
+    /// and cached for the lifetime of the thread, so per-commit processing doesn't
+    /// re-read and re-parse `.mailmap` on every call.
+    static THREAD_REPO: RefCell<Option<(String, Repository, Mailmap)>> = RefCell::new(None);
+}
+
+/// Runs `f` against a `Repository` and its `.mailmap` opened on the current worker
+/// thread, reusing cached handles for `repo_path` when they're already open on this
+/// thread.
+fn with_thread_repo<T>(repo_path: &str, f: impl FnOnce(&Repository, &Mailmap) -> Result<T>) -> Result<T> {
+    THREAD_REPO.with(|cell| {
+        let mut slot = cell.borrow_mut();
+        let needs_open = !matches!(&*slot, Some((cached_path, _, _)) if cached_path == repo_path);
+        if needs_open {
+            let repo = Repository::open(repo_path)
+                .with_context(|| format!("Failed to open repository at {} in worker thread", repo_path))?;
+            let mailmap = repo.mailmap().context("Failed to load mailmap")?;
+            *slot = Some((repo_path.to_string(), repo, mailmap));
+        }
+        let (_, repo, mailmap) = slot.as_ref().expect("repository handle was just populated");
+        f(repo, mailmap)
+    })
+}
+
+/// Analyzes a git repository and returns statistics.
+///
+/// `since_commit_hash`, if given, is the hash of the most recently indexed
+/// commit from a prior run (see `db::get_last_indexed_hash`); the revwalk
+/// stops as soon as it reaches that commit, so only commits newer than it
+/// are diffed and returned. Ignored when a date filter is given: incremental
+/// indexing and ad-hoc date-range queries are independent dimensions, and an
+/// old watermark would otherwise cut a historical `--start-date`/`--end-date`
+/// query short instead of walking back to the requested range.
 pub fn analyze_repository(
     repo_path: &Path,
     start_date_str: Option<&str>,
     end_date_str: Option<&str>,
+    since_commit_hash: Option<&str>,
 ) -> Result<RepositoryStats> {
     // Parse date filters if provided
     let start_date = match start_date_str {
         Some(date_str) => Some(parse_date(date_str)?),
         None => None,
     };
-    
+
     let end_date = match end_date_str {
         Some(date_str) => Some(parse_date(date_str)?),
         None => None,
     };
-    
+
+    let since_commit = if start_date.is_none() && end_date.is_none() {
+        match since_commit_hash {
+            Some(hash) => Some(git2::Oid::from_str(hash).context("Failed to parse last-indexed commit hash")?),
+            None => None,
+        }
+    } else {
+        None
+    };
+
     // Open repository
     let repo_path_str = repo_path.to_string_lossy().to_string();
     let repo = Repository::open(&repo_path_str).context("Failed to open repository")?;
-    
+
     // Create stats object
     let mut stats = RepositoryStats::new(&repo_path.to_string_lossy());
-    
+
+    // Record the branch tip itself (not the newest-by-date commit we'll see
+    // below) as the new incremental-indexing watermark, so the next run
+    // resumes from the actual revwalk start point regardless of how author
+    // dates are ordered relative to topology.
+    //
+    // Only safe to advance when this run wasn't date-filtered: a filtered
+    // run indexes only the date-selected subset of history, so
+    // recording HEAD as the watermark would make a later *unfiltered* run
+    // stop there too and silently skip everything the filter had excluded.
+    if start_date.is_none() && end_date.is_none() {
+        let head_commit = repo.head().context("Failed to resolve HEAD")?
+            .peel_to_commit().context("Failed to peel HEAD to a commit")?;
+        stats.head_hash = Some(head_commit.id().to_string());
+    }
+
     // Get all commits
-    let commits = get_commits(&repo, start_date, end_date)?;
-    
-    // Process commits
-    for commit_id in commits {
-        match process_commit(&repo, commit_id) {
-            Ok(commit_info) => stats.add_commit(commit_info),
+    let commits = get_commits(&repo, start_date, end_date, since_commit)?;
+    drop(repo);
+
+    // Diff each commit in parallel, then fold the results into `stats` in the
+    // original revwalk order so first/last-date and contributor aggregation
+    // stay deterministic regardless of which worker finishes first.
+    for commit_info in process_commits_parallel(&repo_path_str, commits)? {
+        stats.add_commit(commit_info);
+    }
+
+    Ok(stats)
+}
+
+/// Number of worker threads to use for parallel commit diffing.
+fn worker_count() -> usize {
+    std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(4)
+}
+
+/// Computes `CommitInfo` for each OID in parallel, one `Repository` handle per
+/// worker thread, and returns the results in the same order as `oids`.
+fn process_commits_parallel(repo_path: &str, oids: Vec<git2::Oid>) -> Result<Vec<CommitInfo>> {
+    let runtime = tokio::runtime::Runtime::new().context("Failed to create async runtime")?;
+    let repo_path = repo_path.to_string();
+
+    let mut indexed_results: Vec<(usize, Result<CommitInfo>)> = runtime.block_on(async {
+        stream::iter(oids.into_iter().enumerate())
+            .map(|(idx, oid)| {
+                let repo_path = repo_path.clone();
+                async move {
+                    let result = task::spawn_blocking(move || {
+                        with_thread_repo(&repo_path, |repo, mailmap| process_commit(repo, mailmap, oid))
+                    })
+                    .await;
+
+                    let result = match result {
+                        Ok(inner) => inner,
+                        Err(e) => Err(anyhow!("Worker thread panicked while processing a commit: {}", e)),
+                    };
+
+                    (idx, result)
+                }
+            })
+            .buffer_unordered(worker_count())
+            .collect()
+            .await
+    });
+
+    indexed_results.sort_by_key(|(idx, _)| *idx);
+
+    let mut commit_infos = Vec::with_capacity(indexed_results.len());
+    for (_, result) in indexed_results {
+        match result {
+            Ok(commit_info) => commit_infos.push(commit_info),
             Err(e) => eprintln!("Error processing commit: {}", e),
         }
     }
-    
-    Ok(stats)
+
+    Ok(commit_infos)
 }
 
-/// Gets all commits in the repository that match the date filters
+/// Gets all commits in the repository that match the date filters.
+///
+/// Stops walking as soon as `since_commit` is reached, so repeated runs only
+/// pay to diff commits added since the last indexing pass. This is only
+/// correct if every commit newer than `since_commit` is visited before it,
+/// so the walk is ordered topologically rather than left at libgit2's
+/// default (arbitrary) order.
 fn get_commits(
     repo: &Repository,
     start_date: Option<DateTime<Utc>>,
     end_date: Option<DateTime<Utc>>,
+    since_commit: Option<git2::Oid>,
 ) -> Result<Vec<git2::Oid>> {
     // 直接在当前线程上下文中执行
     let mut revwalk = repo.revwalk().context("Failed to create revision walker")?;
     revwalk.push_head().context("Failed to push HEAD to revision walker")?;
-    
+    revwalk.set_sorting(git2::Sort::TOPOLOGICAL | git2::Sort::TIME)
+        .context("Failed to set revision walker sorting")?;
+
     // Collect all commit OIDs that match our date filters
     let mut commits = Vec::new();
     for oid_result in revwalk {
         let oid = oid_result.context("Failed to get commit OID")?;
+
+        if Some(oid) == since_commit {
+            break;
+        }
+
         let commit = repo.find_commit(oid).context("Failed to find commit")?;
-        
+
         let commit_time = commit.time();
         let dt = git_time_to_datetime(commit_time.seconds());
         
@@ -85,20 +207,33 @@ fn get_commits(
 }
 
 /// Processes a single commit to extract its information
-fn process_commit(repo: &Repository, commit_id: git2::Oid) -> Result<CommitInfo> {
+fn process_commit(repo: &Repository, mailmap: &Mailmap, commit_id: git2::Oid) -> Result<CommitInfo> {
     let commit = repo.find_commit(commit_id).context("Failed to find commit")?;
-    
-    // Get commit metadata
-    let author = commit.author();
+
+    // Resolve the commit's identity through .mailmap so aliased names/emails
+    // collapse onto one canonical identity before aggregation. Commits with
+    // no matching mailmap entry pass through unchanged here; they're still
+    // merged downstream by normalized email (see `RepositoryStats::add_commit`).
+    // The mailmap itself is loaded once per worker thread (see `THREAD_REPO`),
+    // not re-parsed on every commit.
+    let author = mailmap
+        .resolve_signature(&commit.author())
+        .context("Failed to resolve author identity via mailmap")?;
     let name = author.name().unwrap_or("Unknown").to_string();
-    let email = author.email().unwrap_or("Unknown").to_string();
+    // Left empty (not a shared "Unknown" sentinel) when the signature has no
+    // email, so `contributor_identity_key` falls back to the author's name
+    // per-commit instead of merging every no-email author into one contributor.
+    let email = author.email().unwrap_or("").to_string();
     let message = commit.message().unwrap_or("").to_string();
     let hash = commit.id().to_string();
     let date = git_time_to_datetime(commit.time().seconds());
     
     // Get diff stats for this commit
-    let (lines_added, lines_removed, files_changed) = get_commit_diff_stats(repo, &commit)?;
-    
+    let (lines_added, lines_removed, files_changed, file_changes) = get_commit_diff_stats(repo, &commit)?;
+
+    // Parse the Conventional Commits header/footer, if present
+    let (commit_type, scope, breaking) = parse_conventional_commit(&message);
+
     Ok(CommitInfo {
         hash,
         author: name,
@@ -108,15 +243,78 @@ fn process_commit(repo: &Repository, commit_id: git2::Oid) -> Result<CommitInfo>
         lines_added,
         lines_removed,
         files_changed,
+        commit_type,
+        scope,
+        breaking,
+        file_changes,
     })
 }
 
-/// Gets diff statistics for a commit
-fn get_commit_diff_stats(repo: &Repository, commit: &git2::Commit) -> Result<(usize, usize, usize)> {
+/// Parses a Conventional Commits header of the form `type(scope)!: description`.
+///
+/// `type` is the leading run of letters before `(`, `!`, or `:`; `(scope)` is
+/// optional; a `!` immediately before `:` marks a breaking change. The footer
+/// (paragraphs after the first blank line) is also scanned for a
+/// `BREAKING CHANGE:` / `BREAKING-CHANGE:` marker. Headers that don't match
+/// the grammar leave the type and scope as `None` and `breaking` as `false`.
+fn parse_conventional_commit(message: &str) -> (Option<String>, Option<String>, bool) {
+    let header = message.lines().next().unwrap_or("");
+
+    let type_end = header
+        .find(|c: char| !c.is_ascii_alphabetic())
+        .filter(|&end| end > 0 && matches!(header.as_bytes()[end], b'(' | b'!' | b':'));
+
+    let Some(type_end) = type_end else {
+        return (None, None, false);
+    };
+
+    let commit_type = header[..type_end].to_string();
+    let mut rest = &header[type_end..];
+
+    let mut scope = None;
+    if let Some(stripped) = rest.strip_prefix('(') {
+        match stripped.find(')') {
+            Some(close) => {
+                scope = Some(stripped[..close].to_string());
+                rest = &stripped[close + 1..];
+            }
+            None => return (None, None, false),
+        }
+    }
+
+    let breaking_marker = rest.starts_with('!');
+    if breaking_marker {
+        rest = &rest[1..];
+    }
+
+    if !rest.starts_with(": ") {
+        return (None, None, false);
+    }
+
+    let breaking = breaking_marker || has_breaking_change_footer(message);
+
+    (Some(commit_type), scope, breaking)
+}
+
+/// Scans the paragraphs after the first blank line for a breaking-change marker.
+fn has_breaking_change_footer(message: &str) -> bool {
+    message
+        .split("\n\n")
+        .skip(1)
+        .any(|paragraph| {
+            let trimmed = paragraph.trim_start();
+            trimmed.starts_with("BREAKING CHANGE:") || trimmed.starts_with("BREAKING-CHANGE:")
+        })
+}
+
+/// Gets diff statistics for a commit, along with per-file line churn
+fn get_commit_diff_stats(repo: &Repository, commit: &git2::Commit) -> Result<(usize, usize, usize, Vec<FileChange>)> {
     let mut lines_added = 0;
     let mut lines_removed = 0;
     let mut files_changed = 0;
-    
+    let mut files = Vec::new();
+    let mut file_line_counts: std::collections::HashMap<String, (usize, usize)> = std::collections::HashMap::new();
+
     // Get parent commit (if any)
     let parent = if commit.parent_count() > 0 {
         Some(commit.parent(0).context("Failed to get parent commit")?)
@@ -150,23 +348,49 @@ fn get_commit_diff_stats(repo: &Repository, commit: &git2::Commit) -> Result<(us
     // Get diff stats
     files_changed = diff.deltas().len();
     
-    // Process each hunk in the diff to count lines added/removed
+    // Process each hunk in the diff to count lines added/removed, and record touched paths
     diff.foreach(
-        &mut |_, _| true,                                // file_cb
+        &mut |delta, _| {
+            if let Some(path) = delta.new_file().path().or_else(|| delta.old_file().path()) {
+                files.push(path.to_string_lossy().to_string());
+            }
+            true
+        },
         None,                                            // binary_cb
         None,                                            // hunk_cb
-        Some(&mut |_delta, _delta_idx, line| {
+        Some(&mut |delta, _delta_idx, line| {
+            let path = delta.new_file().path().or_else(|| delta.old_file().path());
+            let counts = path.map(|p| file_line_counts.entry(p.to_string_lossy().to_string()).or_insert((0, 0)));
+
             match line.origin() {
-                '+' => lines_added += 1,
-                '-' => lines_removed += 1,
+                '+' => {
+                    lines_added += 1;
+                    if let Some(counts) = counts {
+                        counts.0 += 1;
+                    }
+                }
+                '-' => {
+                    lines_removed += 1;
+                    if let Some(counts) = counts {
+                        counts.1 += 1;
+                    }
+                }
                 _ => {}
             }
             true
         }),
     )
     .context("Failed to process diff")?;
-    
-    Ok((lines_added, lines_removed, files_changed))
+
+    let file_changes = files
+        .into_iter()
+        .map(|path| {
+            let (added, removed) = file_line_counts.get(&path).copied().unwrap_or((0, 0));
+            FileChange { path, lines_added: added, lines_removed: removed }
+        })
+        .collect();
+
+    Ok((lines_added, lines_removed, files_changed, file_changes))
 }
 
 /// Converts a git timestamp to a chrono DateTime
@@ -181,7 +405,60 @@ fn git_time_to_datetime(time: i64) -> DateTime<Utc> {
 fn parse_date(date_str: &str) -> Result<DateTime<Utc>> {
     let naive_date = chrono::NaiveDate::parse_from_str(date_str, "%Y-%m-%d")
         .context("Failed to parse date, expected format YYYY-MM-DD")?;
-    
+
     let naive_datetime = naive_date.and_hms_opt(0, 0, 0).unwrap();
     Ok(Utc.from_utc_datetime(&naive_datetime))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_simple_header() {
+        let (commit_type, scope, breaking) = parse_conventional_commit("feat: add login flow");
+        assert_eq!(commit_type.as_deref(), Some("feat"));
+        assert_eq!(scope, None);
+        assert!(!breaking);
+    }
+
+    #[test]
+    fn parses_scope() {
+        let (commit_type, scope, breaking) = parse_conventional_commit("fix(parser): handle empty input");
+        assert_eq!(commit_type.as_deref(), Some("fix"));
+        assert_eq!(scope.as_deref(), Some("parser"));
+        assert!(!breaking);
+    }
+
+    #[test]
+    fn marks_bang_before_colon_as_breaking() {
+        let (commit_type, scope, breaking) = parse_conventional_commit("feat(api)!: drop v1 endpoints");
+        assert_eq!(commit_type.as_deref(), Some("feat"));
+        assert_eq!(scope.as_deref(), Some("api"));
+        assert!(breaking);
+    }
+
+    #[test]
+    fn marks_breaking_change_footer_as_breaking() {
+        let message = "feat: add new export format\n\nBREAKING CHANGE: old format is no longer supported";
+        let (commit_type, _, breaking) = parse_conventional_commit(message);
+        assert_eq!(commit_type.as_deref(), Some("feat"));
+        assert!(breaking);
+    }
+
+    #[test]
+    fn non_conventional_header_yields_none() {
+        let (commit_type, scope, breaking) = parse_conventional_commit("Merge branch 'main' into feature");
+        assert_eq!(commit_type, None);
+        assert_eq!(scope, None);
+        assert!(!breaking);
+    }
+
+    #[test]
+    fn unclosed_scope_is_not_conventional() {
+        let (commit_type, scope, breaking) = parse_conventional_commit("fix(parser: missing close paren");
+        assert_eq!(commit_type, None);
+        assert_eq!(scope, None);
+        assert!(!breaking);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,196 @@
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::models::{GithubActivity, GithubIssue, GithubPullRequest};
+
+const GITHUB_GRAPHQL_URL: &str = "https://api.github.com/graphql";
+const PAGE_SIZE: i64 = 50;
+
+const ISSUES_QUERY: &str = r#"
+query($owner: String!, $name: String!, $first: Int!, $after: String) {
+  repository(owner: $owner, name: $name) {
+    issues(first: $first, after: $after, orderBy: {field: CREATED_AT, direction: ASC}) {
+      pageInfo { hasNextPage endCursor }
+      nodes { number title state createdAt closedAt author { login } }
+    }
+  }
+}
+"#;
+
+const PULL_REQUESTS_QUERY: &str = r#"
+query($owner: String!, $name: String!, $first: Int!, $after: String) {
+  repository(owner: $owner, name: $name) {
+    pullRequests(first: $first, after: $after, orderBy: {field: CREATED_AT, direction: ASC}) {
+      pageInfo { hasNextPage endCursor }
+      nodes { number title state createdAt closedAt author { login } }
+    }
+  }
+}
+"#;
+
+#[derive(Debug, Deserialize)]
+struct GraphQlResponse<T> {
+    data: Option<T>,
+    errors: Option<Vec<GraphQlError>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GraphQlError {
+    message: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct PageInfo {
+    #[serde(rename = "hasNextPage")]
+    has_next_page: bool,
+    #[serde(rename = "endCursor")]
+    end_cursor: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ItemNode {
+    number: i64,
+    title: String,
+    state: String,
+    #[serde(rename = "createdAt")]
+    created_at: DateTime<Utc>,
+    #[serde(rename = "closedAt")]
+    closed_at: Option<DateTime<Utc>>,
+    author: Option<AuthorNode>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AuthorNode {
+    login: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ItemConnection {
+    #[serde(rename = "pageInfo")]
+    page_info: PageInfo,
+    nodes: Vec<ItemNode>,
+}
+
+#[derive(Debug, Deserialize)]
+struct IssuesData {
+    repository: IssuesRepository,
+}
+
+#[derive(Debug, Deserialize)]
+struct IssuesRepository {
+    issues: ItemConnection,
+}
+
+#[derive(Debug, Deserialize)]
+struct PullRequestsData {
+    repository: PullRequestsRepository,
+}
+
+#[derive(Debug, Deserialize)]
+struct PullRequestsRepository {
+    #[serde(rename = "pullRequests")]
+    pull_requests: ItemConnection,
+}
+
+/// Fetches all issues and pull requests for `owner/name` via the GitHub
+/// GraphQL API, authenticating with `token`. Paginates through the full
+/// history using `pageInfo.hasNextPage`/`endCursor` rather than assuming
+/// everything fits in one page.
+pub fn fetch_repo_activity(owner: &str, name: &str, token: &str) -> Result<GithubActivity> {
+    let client = reqwest::blocking::Client::new();
+
+    let issues = fetch_all_pages(&client, token, ISSUES_QUERY, owner, name, |data: IssuesData| {
+        data.repository.issues
+    })?
+    .into_iter()
+    .map(|node| GithubIssue {
+        number: node.number,
+        title: node.title,
+        author: node.author.map(|a| a.login),
+        state: node.state,
+        created_at: node.created_at,
+        closed_at: node.closed_at,
+    })
+    .collect();
+
+    let pull_requests = fetch_all_pages(&client, token, PULL_REQUESTS_QUERY, owner, name, |data: PullRequestsData| {
+        data.repository.pull_requests
+    })?
+    .into_iter()
+    .map(|node| GithubPullRequest {
+        number: node.number,
+        title: node.title,
+        author: node.author.map(|a| a.login),
+        state: node.state,
+        created_at: node.created_at,
+        closed_at: node.closed_at,
+    })
+    .collect();
+
+    Ok(GithubActivity { issues, pull_requests })
+}
+
+/// Runs `query` against the GraphQL API repeatedly, feeding `endCursor` back
+/// in as `after` until `hasNextPage` is false, accumulating every node seen.
+fn fetch_all_pages<T: for<'de> Deserialize<'de>>(
+    client: &reqwest::blocking::Client,
+    token: &str,
+    query: &str,
+    owner: &str,
+    name: &str,
+    extract: impl Fn(T) -> ItemConnection,
+) -> Result<Vec<ItemNode>> {
+    let mut nodes = Vec::new();
+    let mut after: Option<String> = None;
+
+    loop {
+        let body = json!({
+            "query": query,
+            "variables": {
+                "owner": owner,
+                "name": name,
+                "first": PAGE_SIZE,
+                "after": after,
+            }
+        });
+
+        let response: GraphQlResponse<T> = client
+            .post(GITHUB_GRAPHQL_URL)
+            .bearer_auth(token)
+            .header("User-Agent", "repo-activity")
+            .json(&body)
+            .send()
+            .context("Failed to send GitHub GraphQL request")?
+            // Check the HTTP status before decoding the body: a non-2xx
+            // response (bad token, rate limit) otherwise degrades to the
+            // misleading "response had no data" below instead of the real error.
+            .error_for_status()
+            .context("GitHub GraphQL request returned an error status")?
+            .json()
+            .context("Failed to parse GitHub GraphQL response")?;
+
+        if let Some(errors) = response.errors {
+            let messages: Vec<String> = errors.into_iter().map(|e| e.message).collect();
+            bail!("GitHub GraphQL request failed: {}", messages.join("; "));
+        }
+
+        let connection = extract(response.data.context("GitHub GraphQL response had no data")?);
+        let has_next_page = connection.page_info.has_next_page;
+        let end_cursor = connection.page_info.end_cursor;
+
+        nodes.extend(connection.nodes);
+
+        if !has_next_page {
+            break;
+        }
+
+        // `hasNextPage: true` with no cursor would otherwise reset `after` to
+        // `None` and re-fetch the first page forever; treat it as a
+        // malformed response instead of looping without making progress.
+        after = Some(end_cursor.context("GitHub GraphQL reported hasNextPage with no endCursor")?);
+    }
+
+    Ok(nodes)
+}
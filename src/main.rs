@@ -4,6 +4,7 @@ use std::path::PathBuf;
 
 mod git;
 mod db;
+mod github;
 mod models;
 
 #[derive(Parser, Debug)]
@@ -20,6 +21,70 @@ struct Args {
     /// End date for analysis (YYYY-MM-DD)
     #[arg(short, long)]
     end_date: Option<String>,
+
+    /// GitHub repository to enrich with issue/PR activity, as "owner/repo".
+    /// Requires a `GITHUB_TOKEN` environment variable. Omit to stay fully offline.
+    #[arg(long)]
+    github_repo: Option<String>,
+
+    /// Query stored commits by author display name instead of printing the
+    /// summary. Combine with the other `--query-*` flags to narrow further.
+    #[arg(long)]
+    query_author: Option<String>,
+
+    /// Query stored commits by contributor email.
+    #[arg(long)]
+    query_email: Option<String>,
+
+    /// Query stored commits whose diff touched a path containing this substring.
+    #[arg(long)]
+    query_path_contains: Option<String>,
+
+    /// Query stored commits whose message contains this substring.
+    #[arg(long)]
+    query_message_contains: Option<String>,
+
+    /// Query stored commits at or before this date (YYYY-MM-DD).
+    #[arg(long)]
+    query_before: Option<String>,
+
+    /// Query stored commits strictly older than this pagination cursor (a
+    /// commit hash or an RFC3339 date from a previous page's last result).
+    #[arg(long)]
+    query_after: Option<String>,
+
+    /// Query stored commits by Conventional Commits type (e.g. `feat`, `fix`).
+    #[arg(long)]
+    query_commit_type: Option<String>,
+
+    /// Query stored commits touching at least this many lines (added + removed).
+    #[arg(long)]
+    query_min_lines_changed: Option<usize>,
+
+    /// Maximum number of commits to return from a `--query-*` run.
+    #[arg(long)]
+    query_limit: Option<usize>,
+
+    /// Number of matching commits to skip from a `--query-*` run.
+    #[arg(long)]
+    query_offset: Option<usize>,
+}
+
+impl Args {
+    /// True when any `--query-*` flag was given, so the run should
+    /// interrogate the stored commits instead of printing the analysis summary.
+    fn has_query_filters(&self) -> bool {
+        self.query_author.is_some()
+            || self.query_email.is_some()
+            || self.query_path_contains.is_some()
+            || self.query_message_contains.is_some()
+            || self.query_before.is_some()
+            || self.query_after.is_some()
+            || self.query_commit_type.is_some()
+            || self.query_min_lines_changed.is_some()
+            || self.query_limit.is_some()
+            || self.query_offset.is_some()
+    }
 }
 
 fn main() -> Result<()> {
@@ -30,23 +95,114 @@ fn main() -> Result<()> {
     let args = Args::parse();
     
     // Initialize database
-    let db_pool = db::init_db()
+    let mut db_pool = db::init_db()
         .context("Failed to initialize database")?;
-    
+
+    // Only walk commits newer than the last indexed run, if there was one
+    let repo_path_str = args.repo_path.to_string_lossy().to_string();
+    let since_commit_hash = db::get_last_indexed_hash(&db_pool, &repo_path_str)
+        .context("Failed to look up last indexed commit")?;
+
     // Process repository
     let repo_stats = git::analyze_repository(
         &args.repo_path,
         args.start_date.as_deref(),
         args.end_date.as_deref(),
+        since_commit_hash.as_deref(),
     ).context("Failed to analyze repository")?;
-    
+
     // Save results to database
-    db::save_stats(db_pool, &repo_stats)
+    let repo_id = db::save_stats(&mut db_pool, &repo_stats)
         .context("Failed to save stats to database")?;
-    
-    // Print summary
-    print_summary(&repo_stats);
-    
+
+    // A `--query-*` flag means the caller wants to interrogate the commits
+    // already in the store rather than see the summary of this run.
+    if args.has_query_filters() {
+        print_query_results(&db_pool, repo_id, &args)?;
+    } else {
+        // `repo_stats` only holds this run's delta (the whole point of
+        // incremental indexing), so a steady-state run with nothing new to
+        // index would otherwise print zeroed-out totals despite the repo
+        // having a full history already in the database. Reload the merged,
+        // persisted stats instead of printing the raw per-run delta.
+        let merged_stats = db::get_repository_stats(&db_pool, repo_id)
+            .context("Failed to reload merged repository stats")?;
+        print_summary(&merged_stats);
+    }
+
+    // Optionally enrich with GitHub issue/PR activity
+    if let Some(github_repo) = &args.github_repo {
+        let (owner, name) = github_repo
+            .split_once('/')
+            .context("--github-repo must be in the form \"owner/repo\"")?;
+        let token = std::env::var("GITHUB_TOKEN")
+            .context("GITHUB_TOKEN must be set to fetch GitHub activity")?;
+
+        let activity = github::fetch_repo_activity(owner, name, &token)
+            .context("Failed to fetch GitHub issue/PR activity")?;
+
+        println!(
+            "\nFetched {} issues and {} pull requests from GitHub",
+            activity.issues.len(),
+            activity.pull_requests.len()
+        );
+
+        db::save_github_activity(&mut db_pool, repo_id, &activity)
+            .context("Failed to save GitHub activity to database")?;
+    }
+
+    Ok(())
+}
+
+/// Builds a `CommitFilters` from the `--query-*` flags and prints the
+/// matching commits, most recent first, followed by their aggregate totals.
+fn print_query_results(conn: &rusqlite::Connection, repo_id: i64, args: &Args) -> Result<()> {
+    let before = match &args.query_before {
+        Some(date_str) => Some(
+            chrono::NaiveDate::parse_from_str(date_str, "%Y-%m-%d")
+                .context("Failed to parse --query-before, expected format YYYY-MM-DD")?
+                .and_hms_opt(23, 59, 59)
+                .unwrap()
+                .and_utc(),
+        ),
+        None => None,
+    };
+
+    let after = match &args.query_after {
+        Some(cursor) => Some(
+            db::resolve_commit_cursor(conn, cursor)
+                .context("Failed to resolve --query-after cursor")?,
+        ),
+        None => None,
+    };
+
+    let filters = db::CommitFilters {
+        author: args.query_author.clone(),
+        email: args.query_email.clone(),
+        path_contains: args.query_path_contains.clone(),
+        message_contains: args.query_message_contains.clone(),
+        before,
+        after,
+        commit_type: args.query_commit_type.clone(),
+        min_lines_changed: args.query_min_lines_changed,
+        limit: args.query_limit,
+        offset: args.query_offset,
+    };
+
+    let (commits, totals) = db::query_commits(conn, repo_id, &filters)
+        .context("Failed to query commits")?;
+
+    println!("\nQuery results:");
+    println!("--------------");
+    for commit in &commits {
+        println!("{}  {}  {}  +{} -{}", &commit.hash[..commit.hash.len().min(10)], commit.date, commit.author, commit.lines_added, commit.lines_removed);
+    }
+
+    println!(
+        "\n{} commit(s) matched, +{} -{} lines",
+        totals.total_commits, totals.total_lines_added, totals.total_lines_removed
+    );
+
     Ok(())
 }
 
@@ -62,8 +218,21 @@ fn print_summary(stats: &models::RepositoryStats) {
     let mut contributors: Vec<_> = stats.contributors.iter().collect();
     contributors.sort_by(|a, b| b.1.commits.cmp(&a.1.commits));
     
-    for (i, (name, stats)) in contributors.iter().take(5).enumerate() {
-        println!("{:>2}. {}: {} commits, +{} -{}  lines", 
-            i + 1, name, stats.commits, stats.lines_added, stats.lines_removed);
+    for (i, (_, stats)) in contributors.iter().take(5).enumerate() {
+        println!("{:>2}. {}: {} commits, +{} -{}  lines",
+            i + 1, stats.name, stats.commits, stats.lines_added, stats.lines_removed);
+    }
+
+    println!("\nHotspot files (by churn):");
+    let mut files: Vec<_> = stats.file_stats.iter().collect();
+    files.sort_by(|a, b| {
+        let churn_a = a.1.lines_added + a.1.lines_removed;
+        let churn_b = b.1.lines_added + b.1.lines_removed;
+        churn_b.cmp(&churn_a).then(b.1.touches.cmp(&a.1.touches))
+    });
+
+    for (i, (path, file_stats)) in files.iter().take(5).enumerate() {
+        println!("{:>2}. {}: {} touches, +{} -{} lines, {} authors",
+            i + 1, path, file_stats.touches, file_stats.lines_added, file_stats.lines_removed, file_stats.authors.len());
     }
 }
\ No newline at end of file
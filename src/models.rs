@@ -1,6 +1,6 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CommitInfo {
@@ -12,15 +12,71 @@ pub struct CommitInfo {
     pub lines_added: usize,
     pub lines_removed: usize,
     pub files_changed: usize,
+    /// Conventional Commits type (e.g. `feat`, `fix`), if the header matches the grammar.
+    pub commit_type: Option<String>,
+    /// Conventional Commits scope, e.g. the `api` in `feat(api): ...`.
+    pub scope: Option<String>,
+    /// Set when the header carries a `!` before `:`, or the footer has a `BREAKING CHANGE:` note.
+    pub breaking: bool,
+    /// Per-file line changes touched by this commit, as seen in its diff.
+    pub file_changes: Vec<FileChange>,
+}
+
+/// Lines added/removed for a single file within one commit's diff.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileChange {
+    pub path: String,
+    pub lines_added: usize,
+    pub lines_removed: usize,
+}
+
+/// Rolled-up churn for a single file across a repository's whole history.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileStats {
+    pub touches: usize,
+    pub lines_added: usize,
+    pub lines_removed: usize,
+    /// Distinct contributor identities (see [`contributor_identity_key`]) that
+    /// have touched this file, not raw display names, so two aliases of the
+    /// same person merged via `.mailmap`/shared email still count once.
+    pub authors: HashSet<String>,
+    pub last_modified: DateTime<Utc>,
+}
+
+/// Normalizes an email address for use as a contributor identity key, so
+/// aliases that share an email (with or without a `.mailmap` entry) collapse
+/// onto the same contributor.
+pub fn normalize_email(email: &str) -> String {
+    email.trim().to_lowercase()
+}
+
+/// Computes the contributor identity key for a commit: the normalized email
+/// when the commit has one, or `"name:"` followed by the author's display
+/// name otherwise. Falling
+/// back to the name (rather than a shared sentinel like `"Unknown"`) keeps
+/// two different no-email authors from being merged into a single fabricated
+/// contributor.
+pub fn contributor_identity_key(email: &str, name: &str) -> String {
+    let trimmed = email.trim();
+    if trimmed.is_empty() {
+        format!("name:{}", name)
+    } else {
+        normalize_email(trimmed)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ContributorStats {
+    /// Display name for this contributor: the name on the first of their commits
+    /// that `add_commit` sees, which with the newest-first revwalk is their most
+    /// recent commit.
+    pub name: String,
     pub commits: usize,
     pub lines_added: usize,
     pub lines_removed: usize,
     pub first_commit: DateTime<Utc>,
     pub last_commit: DateTime<Utc>,
+    pub breaking_changes: usize,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -32,8 +88,24 @@ pub struct RepositoryStats {
     pub first_commit_date: DateTime<Utc>,
     #[serde(with = "chrono::serde::ts_seconds")]
     pub last_commit_date: DateTime<Utc>,
+    /// Keyed by contributor identity (see [`contributor_identity_key`]): the
+    /// normalized email when a commit has one, `"name:<display name>"` otherwise, so
+    /// aliases that share an email collapse onto one contributor without
+    /// merging distinct no-email authors together.
     pub contributors: HashMap<String, ContributorStats>,
     pub commits: Vec<CommitInfo>,
+    /// Commit counts by Conventional Commits type (e.g. `feat` -> 12, `fix` -> 5).
+    pub commit_type_counts: HashMap<String, usize>,
+    /// Total commits whose header or footer marked them as breaking changes.
+    pub breaking_changes: usize,
+    /// Per-file churn rolled up across the whole history, keyed by path.
+    pub file_stats: HashMap<String, FileStats>,
+    /// Hash of the commit the revwalk started from (the branch tip at the
+    /// time of this run), used to resume incremental indexing from the
+    /// actual tip rather than the newest-by-author-date commit seen, which
+    /// can be an ancestor of the tip when history isn't strictly monotonic
+    /// (rebases, cherry-picks, clock skew, merges).
+    pub head_hash: Option<String>,
 }
 
 impl RepositoryStats {
@@ -47,6 +119,10 @@ impl RepositoryStats {
             last_commit_date: Utc::now(),
             contributors: HashMap::new(),
             commits: Vec::new(),
+            commit_type_counts: HashMap::new(),
+            breaking_changes: 0,
+            file_stats: HashMap::new(),
+            head_hash: None,
         }
     }
 
@@ -64,29 +140,94 @@ impl RepositoryStats {
             self.last_commit_date = commit.date;
         }
         
-        // Update contributor stats
+        // Update contributor stats, merged by normalized email so aliases collapse
+        // (falling back to the author name for commits with no email; see
+        // `contributor_identity_key`)
+        let identity_key = contributor_identity_key(&commit.email, &commit.author);
         let contributor = self.contributors
-            .entry(commit.author.clone())
+            .entry(identity_key)
             .or_insert_with(|| ContributorStats {
+                name: commit.author.clone(),
                 commits: 0,
                 lines_added: 0,
                 lines_removed: 0,
                 first_commit: commit.date,
                 last_commit: commit.date,
+                breaking_changes: 0,
             });
-        
+
         contributor.commits += 1;
         contributor.lines_added += commit.lines_added;
         contributor.lines_removed += commit.lines_removed;
-        
+
         if commit.date < contributor.first_commit {
             contributor.first_commit = commit.date;
         }
         if commit.date > contributor.last_commit {
             contributor.last_commit = commit.date;
         }
-        
+
+        // Update Conventional Commits rollups
+        if let Some(commit_type) = &commit.commit_type {
+            *self.commit_type_counts.entry(commit_type.clone()).or_insert(0) += 1;
+        }
+        if commit.breaking {
+            self.breaking_changes += 1;
+            contributor.breaking_changes += 1;
+        }
+
+        // Update per-file churn rollups
+        for file_change in &commit.file_changes {
+            let file_stat = self.file_stats
+                .entry(file_change.path.clone())
+                .or_insert_with(|| FileStats {
+                    touches: 0,
+                    lines_added: 0,
+                    lines_removed: 0,
+                    authors: HashSet::new(),
+                    last_modified: commit.date,
+                });
+
+            file_stat.touches += 1;
+            file_stat.lines_added += file_change.lines_added;
+            file_stat.lines_removed += file_change.lines_removed;
+            file_stat.authors.insert(identity_key.clone());
+            if commit.date > file_stat.last_modified {
+                file_stat.last_modified = commit.date;
+            }
+        }
+
         // Store commit info
         self.commits.push(commit);
     }
+}
+
+/// A GitHub issue, as fetched from the GraphQL API.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GithubIssue {
+    pub number: i64,
+    pub title: String,
+    pub author: Option<String>,
+    pub state: String,
+    pub created_at: DateTime<Utc>,
+    pub closed_at: Option<DateTime<Utc>>,
+}
+
+/// A GitHub pull request, as fetched from the GraphQL API.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GithubPullRequest {
+    pub number: i64,
+    pub title: String,
+    pub author: Option<String>,
+    pub state: String,
+    pub created_at: DateTime<Utc>,
+    pub closed_at: Option<DateTime<Utc>>,
+}
+
+/// Issue and pull request activity for a repository, fetched independently of
+/// the local git analysis so purely-local runs can skip it entirely.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GithubActivity {
+    pub issues: Vec<GithubIssue>,
+    pub pull_requests: Vec<GithubPullRequest>,
 }
\ No newline at end of file